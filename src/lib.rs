@@ -0,0 +1,6 @@
+pub mod delta;
+pub mod manifest;
+#[cfg(feature = "indicatif")]
+pub mod progress;
+pub mod staging;
+pub mod updater;
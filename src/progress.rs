@@ -0,0 +1,84 @@
+//! A ready-to-use [`ProgressWatcher`] built on `indicatif`, behind the `indicatif` cargo
+//! feature so the core crate stays dependency-light for callers that want to wire up their own
+//! UI (or none at all).
+#![cfg(feature = "indicatif")]
+
+use std::{collections::HashMap, sync::Mutex};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use tough::TargetName;
+
+use crate::updater::{ProgressWatcher, UpdateProgress};
+
+/// Renders a bar per in-flight target plus an overall bar for the whole batch. This saves every
+/// downstream app from reimplementing the spinner/bar wiring by hand.
+pub struct IndicatifProgressWatcher {
+    multi: MultiProgress,
+    overall: ProgressBar,
+    bars: Mutex<HashMap<TargetName, ProgressBar>>,
+}
+
+impl IndicatifProgressWatcher {
+    /// `total_targets` sets the overall bar's length, which is not adjusted as files finish;
+    /// with `0` the bar counts finished files against a length of 0.
+    pub fn new(total_targets: u64) -> Self {
+        let multi = MultiProgress::new();
+        let overall = multi.add(ProgressBar::new(total_targets));
+        overall.set_style(
+            ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} files")
+                .unwrap_or_else(|_| ProgressStyle::default_bar()),
+        );
+
+        Self {
+            multi,
+            overall,
+            bars: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn bar_for(&self, name: &TargetName) -> ProgressBar {
+        let mut bars = self.bars.lock().unwrap();
+        bars.entry(name.clone())
+            .or_insert_with(|| {
+                let bar = self.multi.add(ProgressBar::new(0));
+                bar.set_style(
+                    ProgressStyle::with_template("{msg} {bar:40.green/black} {bytes}/{total_bytes}")
+                        .unwrap_or_else(|_| ProgressStyle::default_bar()),
+                );
+                bar.set_message(name.resolved().to_string());
+                bar
+            })
+            .clone()
+    }
+}
+
+impl std::fmt::Debug for IndicatifProgressWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndicatifProgressWatcher").finish_non_exhaustive()
+    }
+}
+
+impl ProgressWatcher for IndicatifProgressWatcher {
+    fn update_progress(&self, progress: UpdateProgress) {
+        match progress {
+            UpdateProgress::StartFileDownload(name) => {
+                self.bar_for(&name);
+            }
+            UpdateProgress::UpdateFileProgress(name, downloaded, total) => {
+                let bar = self.bar_for(&name);
+                bar.set_length(total);
+                bar.set_position(downloaded);
+            }
+            UpdateProgress::FinishFileDownload => {
+                self.overall.inc(1);
+            }
+            UpdateProgress::FinishUpdate => {
+                let bars = self.bars.lock().unwrap();
+                for bar in bars.values() {
+                    bar.finish_and_clear();
+                }
+                self.overall.finish_with_message("update complete");
+            }
+        }
+    }
+}
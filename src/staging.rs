@@ -0,0 +1,249 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tough::TargetName;
+use uuid::Uuid;
+
+/// One entry in a commit's rollback journal: the final path a staged file was moved to, and
+/// (if something already lived there) the path it was backed up to before being overwritten.
+#[derive(Serialize, Deserialize, Debug)]
+struct JournalEntry {
+    final_path: PathBuf,
+    backup_path: Option<PathBuf>,
+}
+
+/// Name of the marker file under `dist_dir/.staging` that records which batch directory is
+/// currently in flight, so a crashed run's partially-downloaded files can be found again
+/// (and resumed, see `Updater`'s range-request support) instead of restarting from scratch.
+const CURRENT_MARKER: &str = "CURRENT";
+
+/// A single update batch staged under `dist_dir/.staging/<uuid>`. Targets are downloaded and
+/// verified into this directory first; only [`StagingBatch::commit`] moves them into `dist_dir`,
+/// and it journals each move to disk so a move that fails (rolled back in memory by
+/// [`Self::rollback`]) or a crash mid-commit (rolled back by the next [`Self::begin`]) can be
+/// undone.
+pub struct StagingBatch {
+    dir: PathBuf,
+    marker: PathBuf,
+}
+
+impl StagingBatch {
+    /// Resumes the batch directory left behind by a crashed run, if any, so its partially
+    /// downloaded files survive; otherwise creates a fresh, uniquely-named staging directory
+    /// under `dist_dir/.staging`. If the crash happened mid-`commit` (a `journal.json` is still
+    /// sitting in the resumed directory), finishes rolling that interrupted commit back first,
+    /// so the batch is left in the same pre-commit state a retry can safely resume from.
+    pub fn begin(dist_dir: &Path) -> anyhow::Result<Self> {
+        let root = dist_dir.join(".staging");
+        fs::create_dir_all(&root)?;
+        let marker = root.join(CURRENT_MARKER);
+
+        let dir = match fs::read_to_string(&marker) {
+            Ok(id) if root.join(id.trim()).is_dir() => root.join(id.trim()),
+            _ => {
+                let dir = root.join(Uuid::new_v4().to_string());
+                fs::create_dir_all(&dir)?;
+                fs::write(&marker, dir.file_name().unwrap().to_string_lossy().as_bytes())?;
+                dir
+            }
+        };
+
+        Self::recover_interrupted_commit(&dir)?;
+
+        Ok(Self { dir, marker })
+    }
+
+    /// Rolls back a `journal.json` left behind by a `commit` that crashed partway through, if
+    /// one is present. A no-op when the prior run's batch directory is fresh or its last commit
+    /// finished cleanly (see [`Self::commit`], which removes the journal on success).
+    fn recover_interrupted_commit(dir: &Path) -> anyhow::Result<()> {
+        let journal_path = dir.join("journal.json");
+        let bytes = match fs::read(&journal_path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let journal: Vec<JournalEntry> = serde_json::from_slice(&bytes)?;
+        Self::rollback(&journal);
+        let _ = fs::remove_file(&journal_path);
+        Ok(())
+    }
+
+    /// The path a target should be downloaded to while staged. Left in place across runs when
+    /// a download fails partway through, so it can be resumed instead of restarted.
+    pub fn staged_path(&self, name: &TargetName) -> PathBuf {
+        self.dir.join(name.resolved())
+    }
+
+    /// Moves every staged target named in `names` into `dist_dir`, journaling each move so a
+    /// failure partway through can be rolled back. Each move first backs up whatever already
+    /// occupies the final path, then renames the staged file into place; a `rename` onto an
+    /// existing path is atomic on the same filesystem.
+    ///
+    /// Only the files named in `names` are touched: anything else left in the batch directory
+    /// (such as a partial download whose transfer was cut off) is kept so it can be resumed on
+    /// the next `update()`. The batch directory and its marker are only removed once it's empty.
+    pub fn commit(self, dist_dir: &Path, names: &[TargetName]) -> anyhow::Result<()> {
+        let journal_path = self.dir.join("journal.json");
+        let mut journal: Vec<JournalEntry> = Vec::new();
+
+        let result = self.commit_inner(dist_dir, names, &journal_path, &mut journal);
+        if result.is_err() {
+            Self::rollback(&journal);
+            return result;
+        }
+
+        for entry in &journal {
+            if let Some(backup_path) = &entry.backup_path {
+                let _ = fs::remove_file(backup_path);
+            }
+        }
+
+        let _ = fs::remove_file(&journal_path);
+        if fs::read_dir(&self.dir).map(|mut d| d.next().is_none()).unwrap_or(false) {
+            let _ = fs::remove_dir_all(&self.dir);
+            let _ = fs::remove_file(&self.marker);
+        }
+
+        result
+    }
+
+    fn commit_inner(
+        &self,
+        dist_dir: &Path,
+        names: &[TargetName],
+        journal_path: &Path,
+        journal: &mut Vec<JournalEntry>,
+    ) -> anyhow::Result<()> {
+        fs::create_dir_all(dist_dir)?;
+
+        for name in names {
+            let staged_path = self.staged_path(name);
+            let final_path = dist_dir.join(name.resolved());
+
+            let backup_path = if final_path.exists() {
+                let backup_path = self.dir.join(format!("{}.bak", name.resolved()));
+                fs::rename(&final_path, &backup_path)?;
+                Some(backup_path)
+            } else {
+                None
+            };
+
+            journal.push(JournalEntry {
+                final_path: final_path.clone(),
+                backup_path,
+            });
+            fs::write(journal_path, serde_json::to_vec(journal)?)?;
+
+            fs::rename(&staged_path, &final_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Restores every already-moved file to its prior state, in reverse commit order.
+    fn rollback(journal: &[JournalEntry]) {
+        for entry in journal.iter().rev() {
+            match &entry.backup_path {
+                Some(backup_path) if backup_path.exists() => {
+                    let _ = fs::rename(backup_path, &entry.final_path);
+                }
+                Some(_) => {}
+                None => {
+                    let _ = fs::remove_file(&entry.final_path);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dist_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tuf_update_staging_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn commit_moves_staged_targets_and_cleans_up_backups() {
+        let dist_dir = temp_dist_dir();
+        let name = TargetName::new("app.bin").unwrap();
+
+        fs::write(dist_dir.join(name.resolved()), b"old").unwrap();
+
+        let batch = StagingBatch::begin(&dist_dir).unwrap();
+        fs::write(batch.staged_path(&name), b"new").unwrap();
+        let batch_dir = batch.dir.clone();
+
+        batch.commit(&dist_dir, &[name.clone()]).unwrap();
+
+        assert_eq!(fs::read(dist_dir.join(name.resolved())).unwrap(), b"new");
+        assert!(!batch_dir.exists(), "batch dir and its .bak backup should be cleaned up");
+        assert!(!dist_dir.join(".staging").join("CURRENT").exists());
+
+        fs::remove_dir_all(&dist_dir).unwrap();
+    }
+
+    #[test]
+    fn rollback_restores_prior_file_after_a_failed_move() {
+        let dist_dir = temp_dist_dir();
+        let good_name = TargetName::new("good.bin").unwrap();
+        let missing_name = TargetName::new("missing.bin").unwrap();
+
+        fs::write(dist_dir.join(good_name.resolved()), b"old").unwrap();
+
+        let batch = StagingBatch::begin(&dist_dir).unwrap();
+        fs::write(batch.staged_path(&good_name), b"new").unwrap();
+        // Deliberately don't write a staged file for `missing_name`, so the rename for it fails
+        // and forces a rollback partway through the batch.
+
+        let result = batch.commit(&dist_dir, &[good_name.clone(), missing_name]);
+        assert!(result.is_err());
+
+        assert_eq!(fs::read(dist_dir.join(good_name.resolved())).unwrap(), b"old");
+
+        fs::remove_dir_all(&dist_dir).unwrap();
+    }
+
+    #[test]
+    fn begin_rolls_back_a_journal_left_by_a_crashed_commit() {
+        let dist_dir = temp_dist_dir();
+        let name = TargetName::new("app.bin").unwrap();
+        let final_path = dist_dir.join(name.resolved());
+        fs::write(&final_path, b"old").unwrap();
+
+        let batch = StagingBatch::begin(&dist_dir).unwrap();
+        let batch_dir = batch.dir.clone();
+        let marker = batch.marker.clone();
+
+        // Simulate a crash partway through `commit_inner`: the old file has been backed up and
+        // the journal written, but the staged file was never renamed into place.
+        let backup_path = batch_dir.join(format!("{}.bak", name.resolved()));
+        fs::rename(&final_path, &backup_path).unwrap();
+        let journal = vec![JournalEntry {
+            final_path: final_path.clone(),
+            backup_path: Some(backup_path.clone()),
+        }];
+        fs::write(
+            batch_dir.join("journal.json"),
+            serde_json::to_vec(&journal).unwrap(),
+        )
+        .unwrap();
+        // Re-point CURRENT at this batch dir, since `begin` above may have created a different one.
+        fs::write(&marker, batch_dir.file_name().unwrap().to_string_lossy().as_bytes()).unwrap();
+
+        let resumed = StagingBatch::begin(&dist_dir).unwrap();
+
+        assert_eq!(fs::read(&final_path).unwrap(), b"old");
+        assert!(!resumed.dir.join("journal.json").exists());
+
+        fs::remove_dir_all(&dist_dir).unwrap();
+    }
+}
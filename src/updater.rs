@@ -1,33 +1,51 @@
 use std::{
     collections::HashSet,
-    fs::File,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
     path::{PathBuf, Path},
     time::{Duration, Instant},
 };
 
 use derive_builder::Builder;
+use rayon::prelude::*;
+use reqwest::{
+    blocking::Client,
+    header::{CONTENT_RANGE, RANGE},
+    StatusCode,
+};
+use ring::digest;
 use snafu::GenerateImplicitData;
 use tough::{schema::Target, Repository, RepositoryLoader, TargetName};
 use url::Url;
 
 use crate::manifest::Manifest;
+use crate::staging::StagingBatch;
 
 pub type UpdateError = anyhow::Error;
 
+/// Size of the chunks read from a target's reader and fed into the SHA256 context.
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
 // TODO: might aswell use a better error type
 fn create_update_error(name: &TargetName, err: tough::error::Error) -> UpdateError {
     anyhow::Error::from(err).context(format!("failed to update target: {}", name.resolved()))
 }
 
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 #[derive(Debug)]
 pub enum UpdateProgress {
     StartFileDownload(TargetName),
-    UpdateFileProgress(u64, u64),
+    UpdateFileProgress(TargetName, u64, u64),
     FinishFileDownload,
     FinishUpdate,
 }
 
-pub trait ProgressWatcher: std::fmt::Debug {
+/// Must be `Send + Sync` because concurrent downloads (see `max_concurrency`) report progress
+/// from multiple worker threads at once.
+pub trait ProgressWatcher: std::fmt::Debug + Send + Sync {
     fn update_progress(&self, progress: UpdateProgress);
 }
 
@@ -36,6 +54,20 @@ pub struct UpdateReport {
     pub updated_files: usize,
     pub deleted_files: usize,
     pub update_time: Duration,
+    pub bytes_transferred: u64,
+}
+
+impl UpdateReport {
+    /// Effective download throughput for this update, in bytes/second. `0.0` if no time was
+    /// measurable or nothing was downloaded (e.g. every target was already up to date).
+    pub fn bytes_per_second(&self) -> f64 {
+        let secs = self.update_time.as_secs_f64();
+        if secs > 0.0 {
+            self.bytes_transferred as f64 / secs
+        } else {
+            0.0
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -48,6 +80,17 @@ pub enum UpdateResult {
     CompleteUpdate(UpdateReport),
 }
 
+/// Result of reconciling `dist_dir` against the `Manifest`: what `verify()` found, and (when
+/// produced by `repair()`) how many of those findings were fixed by re-fetching the target.
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub verified: usize,
+    pub mismatched: usize,
+    pub missing: usize,
+    pub extra: usize,
+    pub repaired: usize,
+}
+
 #[derive(Builder, Debug)]
 #[builder(setter(into), pattern = "owned")]
 pub struct Updater {
@@ -56,21 +99,52 @@ pub struct Updater {
     dist_dir: PathBuf,
     #[builder(default)]
     watcher: Option<Box<dyn ProgressWatcher>>,
-    safe_delete_exe_target: String
+    safe_delete_exe_target: String,
+    /// When set, targets are downloaded and verified into a staging directory and only moved
+    /// into `dist_dir` once the whole batch has succeeded, so a crash mid-update can't leave
+    /// `dist_dir` with some targets updated and others not.
+    #[builder(default)]
+    atomic: bool,
+    /// Number of targets downloaded and verified in parallel. `1` (the default) preserves the
+    /// original sequential behavior; anything higher runs downloads through a bounded worker
+    /// pool and always commits the batch atomically, since concurrent writers need the same
+    /// staging isolation `atomic` provides.
+    #[builder(default = "1")]
+    max_concurrency: usize,
+    /// Base URL targets are served from. Only used to resume an interrupted download with an
+    /// HTTP `Range` request; when absent, interrupted downloads simply restart from zero.
+    #[builder(default)]
+    targets_base_url: Option<Url>,
+    /// When set, look for a patch target named `<name>.patch.<from>-<to>`, where `<from>`/`<to>`
+    /// are the hex SHA256 of the old and new target (see [`Self::delta_patch_name`]) — not a
+    /// version number, since the `Manifest` doesn't track one — before falling back to
+    /// downloading the full target.
+    #[builder(default)]
+    allow_delta: bool,
 }
 
 impl Updater {
-    pub fn load_basic_http_repo(base_url: &str, tuf_dir: impl AsRef<Path>) -> anyhow::Result<Repository> {
+    /// Returns the loaded repository along with the targets base URL, which callers should feed
+    /// into `UpdaterBuilder::targets_base_url` to enable resumable downloads.
+    pub fn load_basic_http_repo(
+        base_url: &str,
+        tuf_dir: impl AsRef<Path>,
+    ) -> anyhow::Result<(Repository, Url)> {
         let base_url = Url::parse(base_url)?;
         let tuf_dir = tuf_dir.as_ref().to_path_buf();
-        Ok(RepositoryLoader::new(
+        // Trailing slash matters: `Url::join` treats the last path segment of its base as a
+        // file name and replaces it, so a bare "/targets" would turn a join of "app.bin" into
+        // ".../app.bin" instead of ".../targets/app.bin".
+        let targets_url = base_url.join("/targets/")?;
+        let repo = RepositoryLoader::new(
             // Root json in the tuf directory
             File::open(tuf_dir.join("root.json"))?,
             base_url.join("/metadata")?,
-            base_url.join("/targets")?,
+            targets_url.clone(),
         )
         .datastore(tuf_dir.join("dist"))
-        .load()?)
+        .load()?;
+        Ok((repo, targets_url))
     }
 
     pub fn repo(&self) -> &Repository {
@@ -83,50 +157,498 @@ impl Updater {
         }
     }
 
+    /// Downloads `target` if needed. Returns whether a download actually happened, so callers
+    /// can tell a freshly-updated target apart from one that was already up to date.
     fn update_target(
         &self,
         manifest: &mut Manifest,
         (name, target): (&TargetName, &Target),
-    ) -> Result<(), UpdateError> {
+    ) -> Result<bool, UpdateError> {
         if manifest.is_target_updated(name, target.length, &target.hashes.sha256) {
-            return Ok(());
+            return Ok(false);
         }
 
         self.update_progress(UpdateProgress::StartFileDownload(name.clone()));
 
-        // TODO: download
-        self.update_progress(UpdateProgress::UpdateFileProgress(50, 100));
+        // `dest_path` is the live file already in `dist_dir`, not a staging partial: it may well
+        // be the still-valid *old* version of the target, which range-resuming against would
+        // waste a round trip only to fail verification. Resuming is only safe for the staged
+        // paths used by the atomic/concurrent update paths.
+        let dest_path = self.dist_dir.join(name.resolved());
+        self.download_target(name, target, &dest_path, manifest, false)?;
 
-        // Determine if self delete is required 
+        // Determine if self delete is required
         if self.safe_delete_exe_target == name.resolved() {
             self_replace::self_delete().expect("Self delete");
             println!("Safe delete: {}", name.resolved());
         }
 
-
-        self.repo
-            .save_target(name, &self.dist_dir, tough::Prefix::None)
-            .map_err(|err| create_update_error(name, err))?;
-
         self.update_progress(UpdateProgress::FinishFileDownload);
 
         manifest.set_target(name, target.length, &target.hashes.sha256);
-        Ok(())
+        Ok(true)
+    }
+
+    /// Streams `target` into `dest_path`, hashing every chunk as it arrives so progress and
+    /// integrity verification come from the same pass over the bytes. If `allow_resume` is set
+    /// and `dest_path` already holds the whole target with the signed digest (staged by an earlier
+    /// batch that was never committed), returns without downloading anything. If `allow_delta` is set
+    /// and a matching patch target is published, applies that instead of fetching the full
+    /// target (see [`Self::try_delta_download`]). Otherwise, if `allow_resume` is set and a
+    /// partial file is already sitting at `dest_path` (left behind by an interrupted previous
+    /// attempt), resumes it with an HTTP range request instead of restarting from zero. Falls
+    /// back to a fresh full download whenever neither shortcut is possible or doesn't pan out.
+    /// The written file is deleted and an error returned if the final length or digest don't
+    /// match what the signed metadata promised.
+    ///
+    /// `allow_resume` must only be set when `dest_path` is a staging path that holds nothing but
+    /// downloads-in-progress (see the atomic/concurrent update paths): a shorter file at a *live*
+    /// `dist_dir` path is the previous, still-valid version of the target, not a partial one, and
+    /// range-resuming against it would just waste a round trip before falling back anyway.
+    fn download_target(
+        &self,
+        name: &TargetName,
+        target: &Target,
+        dest_path: &Path,
+        manifest: &Manifest,
+        allow_resume: bool,
+    ) -> Result<(), UpdateError> {
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        if allow_resume {
+            if let Ok(Some((len, hash))) = Self::hash_file(dest_path) {
+                if len == target.length && hash == *target.hashes.sha256 {
+                    return Ok(());
+                }
+            }
+        }
+
+        if self.allow_delta && self.try_delta_download(name, target, dest_path, manifest).is_ok() {
+            return Ok(());
+        }
+
+        let existing_len = std::fs::metadata(dest_path).map(|m| m.len()).unwrap_or(0);
+        if allow_resume && existing_len > 0 && existing_len < target.length {
+            match self.resume_download(name, target, dest_path, existing_len) {
+                Ok(()) => return Ok(()),
+                Err(_) => {
+                    // Server didn't honor the range, or the resumed bytes didn't check out;
+                    // restart the whole target rather than leaving a questionable partial file.
+                    let _ = std::fs::remove_file(dest_path);
+                }
+            }
+        }
+
+        let mut reader = self
+            .repo
+            .read_target(name)
+            .map_err(|err| create_update_error(name, err))?
+            .ok_or_else(|| anyhow::anyhow!("target not found in repository: {}", name.resolved()))?;
+
+        let file = File::create(dest_path)?;
+        self.copy_and_verify(
+            name,
+            target,
+            &mut *reader,
+            dest_path,
+            file,
+            0,
+            digest::Context::new(&digest::SHA256),
+        )
+    }
+
+    /// Picks up a target whose download was interrupted partway through: re-hashes the bytes
+    /// already on disk to seed the running SHA256 context, then issues the fetch with a
+    /// `Range: bytes=<n>-` header and appends the response to the existing file. Bails out
+    /// (leaving the caller to restart the target from scratch) if the server's `Content-Range`
+    /// doesn't confirm the response actually starts at `existing_len`, since appending a
+    /// differently-aligned range would silently corrupt the file.
+    fn resume_download(
+        &self,
+        name: &TargetName,
+        target: &Target,
+        dest_path: &Path,
+        existing_len: u64,
+    ) -> Result<(), UpdateError> {
+        let base_url = self
+            .targets_base_url
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no targets base URL configured for resume"))?;
+        let url = base_url.join(name.resolved())?;
+
+        let mut ctx = digest::Context::new(&digest::SHA256);
+        let mut existing = File::open(dest_path)?;
+        let mut buf = [0u8; DOWNLOAD_CHUNK_SIZE];
+        loop {
+            let n = existing.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            ctx.update(&buf[..n]);
+        }
+        drop(existing);
+
+        let response = Client::new()
+            .get(url)
+            .header(RANGE, format!("bytes={}-", existing_len))
+            .send()?;
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            anyhow::bail!(
+                "server did not honor range request for target {}",
+                name.resolved()
+            );
+        }
+
+        let range_start = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("bytes "))
+            .and_then(|value| value.split(['-', '/']).next())
+            .and_then(|value| value.parse::<u64>().ok());
+        if range_start != Some(existing_len) {
+            anyhow::bail!(
+                "server returned a Content-Range starting at {:?} for target {}, expected {}",
+                range_start,
+                name.resolved(),
+                existing_len
+            );
+        }
+
+        let mut response = response;
+        let file = OpenOptions::new().append(true).open(dest_path)?;
+        self.copy_and_verify(name, target, &mut response, dest_path, file, existing_len, ctx)
+    }
+
+    /// Shared tail of both the fresh and resumed download paths: copies chunks from `reader`
+    /// into `file`, hashing and reporting progress as it goes, then verifies the finished file
+    /// against the signed length and SHA256 digest. If the finished file fails that check,
+    /// `dest_path` is deleted rather than left behind as a corrupt artifact; if reading or writing
+    /// fails partway, the partial file is left in place so a resuming download can pick it up.
+    fn copy_and_verify(
+        &self,
+        name: &TargetName,
+        target: &Target,
+        reader: &mut dyn Read,
+        dest_path: &Path,
+        mut file: File,
+        mut written: u64,
+        mut ctx: digest::Context,
+    ) -> Result<(), UpdateError> {
+        let result = (|| -> Result<(), UpdateError> {
+            let mut buf = [0u8; DOWNLOAD_CHUNK_SIZE];
+
+            loop {
+                let n = reader.read(&mut buf)?;
+                if n == 0 {
+                    break;
+                }
+
+                file.write_all(&buf[..n])?;
+                ctx.update(&buf[..n]);
+                written += n as u64;
+
+                self.update_progress(UpdateProgress::UpdateFileProgress(
+                    name.clone(),
+                    written,
+                    target.length,
+                ));
+            }
+            file.flush()?;
+            drop(file);
+
+            if written != target.length {
+                let _ = std::fs::remove_file(dest_path);
+                return Err(anyhow::anyhow!(
+                    "corrupt download for target {}: expected {} bytes, got {}",
+                    name.resolved(),
+                    target.length,
+                    written,
+                ));
+            }
+
+            let digest = ctx.finish();
+            if digest.as_ref() != &*target.hashes.sha256 {
+                let _ = std::fs::remove_file(dest_path);
+                return Err(anyhow::anyhow!(
+                    "corrupt download for target {}: SHA256 mismatch, expected {} got {}",
+                    name.resolved(),
+                    hex_encode(&target.hashes.sha256),
+                    hex_encode(digest.as_ref()),
+                ));
+            }
+
+            Ok(())
+        })();
+
+        result
+    }
+
+    /// Builds the by-convention name of the patch target that would bring `name` from `from`
+    /// (hex SHA256) to `to` (hex SHA256): `<name>.patch.<from>-<to>`.
+    ///
+    /// Note this keys on hex-encoded SHA256 digests, not version numbers, even though patches
+    /// are conventionally described as `<name>.patch.<from_version>-<to_version>`: the
+    /// `Manifest` tracks a length+hash per target, not a version, so a digest is the only value
+    /// on hand to key from. Publishers generating patch targets for this updater need to name
+    /// them with the hex SHA256 of the old and new target, not a version string.
+    fn delta_patch_name(name: &TargetName, from: &[u8], to: &[u8]) -> anyhow::Result<TargetName> {
+        let patch_name = format!(
+            "{}.patch.{}-{}",
+            name.resolved(),
+            hex_encode(from),
+            hex_encode(to),
+        );
+        TargetName::new(patch_name).map_err(anyhow::Error::from)
+    }
+
+    /// Looks for a patch target named by the `delta_patch_name` convention from the version
+    /// already on disk (per the `Manifest`) to `target`, and applies it in place of a full
+    /// download. The reconstructed file is verified against the full target's signed length and
+    /// SHA256 before being accepted, so a bad or stale patch is caught the same way a corrupt
+    /// full download would be; callers should fall back to a full download on any error here.
+    fn try_delta_download(
+        &self,
+        name: &TargetName,
+        target: &Target,
+        dest_path: &Path,
+        manifest: &Manifest,
+    ) -> Result<(), UpdateError> {
+        let current = manifest.files().get(name).ok_or_else(|| {
+            anyhow::anyhow!("no prior version of {} to patch from", name.resolved())
+        })?;
+
+        let old_path = self.dist_dir.join(name.resolved());
+        if !old_path.exists() {
+            anyhow::bail!("no local file for {} to patch", name.resolved());
+        }
+
+        let patch_name = Self::delta_patch_name(name, &current.hash, &target.hashes.sha256)?;
+        if !self.repo.targets().signed.targets.contains_key(&patch_name) {
+            anyhow::bail!("no patch target {} available", patch_name.resolved());
+        }
+
+        let mut patch_reader = self
+            .repo
+            .read_target(&patch_name)
+            .map_err(|err| create_update_error(&patch_name, err))?
+            .ok_or_else(|| anyhow::anyhow!("patch target disappeared: {}", patch_name.resolved()))?;
+
+        let staged_path = dest_path.with_file_name(format!("{}.patching", name.resolved()));
+        {
+            let mut old_file = File::open(&old_path)?;
+            let mut out_file = File::create(&staged_path)?;
+            crate::delta::apply_patch(&mut old_file, &mut *patch_reader, &mut out_file)?;
+        }
+
+        match Self::hash_file(&staged_path) {
+            Ok(Some((len, hash))) if len == target.length && hash == *target.hashes.sha256 => {
+                std::fs::rename(&staged_path, dest_path)?;
+                Ok(())
+            }
+            _ => {
+                let _ = std::fs::remove_file(&staged_path);
+                Err(anyhow::anyhow!(
+                    "patched file for {} failed verification",
+                    name.resolved()
+                ))
+            }
+        }
+    }
+
+    fn update_all_targets(&mut self, manifest: &mut Manifest) -> (usize, u64, Vec<UpdateError>) {
+        if self.max_concurrency > 1 {
+            self.update_all_targets_concurrent(manifest)
+        } else if self.atomic {
+            self.update_all_targets_atomic(manifest)
+        } else {
+            self.update_all_targets_direct(manifest)
+        }
     }
 
-    fn update_all_targets(&mut self, manifest: &mut Manifest) -> (usize, Vec<UpdateError>) {
+    fn update_all_targets_direct(&mut self, manifest: &mut Manifest) -> (usize, u64, Vec<UpdateError>) {
         let targets = &self.repo.targets().signed;
 
         let mut errs = vec![];
+        let mut bytes = 0u64;
+        let mut updated_files = 0usize;
         for (name, target) in targets.targets_iter() {
-            if let Err(err) = self.update_target(manifest, (name, target)) {
-                errs.push(err);
+            match self.update_target(manifest, (name, target)) {
+                Ok(true) => {
+                    updated_files += 1;
+                    bytes += target.length;
+                }
+                Ok(false) => {}
+                Err(err) => errs.push(err),
+            }
+        }
+        self.update_progress(UpdateProgress::FinishUpdate);
+
+        (updated_files, bytes, errs)
+    }
+
+    /// Downloads and verifies every changed target into a staging directory, then — only once
+    /// every one of them has verified — moves the whole batch into `dist_dir` in a single commit
+    /// pass, so a crash (or a download/verify failure) never leaves `dist_dir` half-updated. If
+    /// any target fails, the commit is skipped entirely: nothing moves, the targets that did
+    /// verify stay staged for the next `update()` to pick up, and the failures are returned.
+    /// See [`StagingBatch`] for the commit/rollback mechanics.
+    fn update_all_targets_atomic(&mut self, manifest: &mut Manifest) -> (usize, u64, Vec<UpdateError>) {
+        let targets = &self.repo.targets().signed;
+        let pending: Vec<(&TargetName, &Target)> = targets
+            .targets_iter()
+            .filter(|(name, target)| {
+                !manifest.is_target_updated(name, target.length, &target.hashes.sha256)
+            })
+            .collect();
+
+        let batch = match StagingBatch::begin(&self.dist_dir) {
+            Ok(batch) => batch,
+            Err(err) => {
+                self.update_progress(UpdateProgress::FinishUpdate);
+                return (0, 0, vec![err.context("failed to create staging area")]);
+            }
+        };
+
+        let mut errs = vec![];
+        let mut staged: Vec<(&TargetName, &Target)> = vec![];
+        for (name, target) in pending {
+            self.update_progress(UpdateProgress::StartFileDownload(name.clone()));
+            match self.download_target(name, target, &batch.staged_path(name), manifest, true) {
+                Ok(()) => {
+                    self.update_progress(UpdateProgress::FinishFileDownload);
+                    staged.push((name, target));
+                }
+                Err(err) => errs.push(err),
+            }
+        }
+
+        // Only commit if every pending target made it into staging: moving a partial batch into
+        // `dist_dir` would leave it with some targets updated and others not, exactly what the
+        // staged-commit design exists to prevent. The targets that did verify stay in the batch
+        // directory and are resumed on the next `update()` (see `StagingBatch::begin`).
+        if !errs.is_empty() {
+            self.update_progress(UpdateProgress::FinishUpdate);
+            return (0, 0, errs);
+        }
+
+        for (name, _) in &staged {
+            if self.safe_delete_exe_target == name.resolved() {
+                self_replace::self_delete().expect("Self delete");
+                println!("Safe delete: {}", name.resolved());
+            }
+        }
+
+        let staged_names: Vec<TargetName> = staged.iter().map(|(name, _)| (*name).clone()).collect();
+        if let Err(err) = batch.commit(&self.dist_dir, &staged_names) {
+            errs.push(err.context("failed to commit staged update"));
+            self.update_progress(UpdateProgress::FinishUpdate);
+            return (0, 0, errs);
+        }
+
+        for (name, target) in &staged {
+            manifest.set_target(name, target.length, &target.hashes.sha256);
+        }
+
+        let updated_files = staged.len();
+        let bytes = staged.iter().map(|(_, target)| target.length).sum();
+        self.update_progress(UpdateProgress::FinishUpdate);
+
+        (updated_files, bytes, errs)
+    }
+
+    /// Same staged-commit shape as [`Self::update_all_targets_atomic`], but the download+verify
+    /// pass runs across a bounded pool of `max_concurrency` worker threads. The commit pass,
+    /// self-delete check, and manifest mutation all stay single-threaded afterwards since the
+    /// `Manifest` is shared mutable state.
+    fn update_all_targets_concurrent(&mut self, manifest: &mut Manifest) -> (usize, u64, Vec<UpdateError>) {
+        let targets = &self.repo.targets().signed;
+        let pending: Vec<(&TargetName, &Target)> = targets
+            .targets_iter()
+            .filter(|(name, target)| {
+                !manifest.is_target_updated(name, target.length, &target.hashes.sha256)
+            })
+            .collect();
+
+        let batch = match StagingBatch::begin(&self.dist_dir) {
+            Ok(batch) => batch,
+            Err(err) => {
+                self.update_progress(UpdateProgress::FinishUpdate);
+                return (0, 0, vec![err.context("failed to create staging area")]);
+            }
+        };
+
+        let pool = match rayon::ThreadPoolBuilder::new()
+            .num_threads(self.max_concurrency)
+            .build()
+        {
+            Ok(pool) => pool,
+            Err(err) => {
+                self.update_progress(UpdateProgress::FinishUpdate);
+                return (
+                    0,
+                    0,
+                    vec![anyhow::Error::new(err).context("failed to start download worker pool")],
+                );
+            }
+        };
+
+        let this = &*self;
+        let manifest_ref = &*manifest;
+        let results: Vec<((&TargetName, &Target), Result<(), UpdateError>)> = pool.install(|| {
+            pending
+                .into_par_iter()
+                .map(|(name, target)| {
+                    this.update_progress(UpdateProgress::StartFileDownload(name.clone()));
+                    let result = this.download_target(name, target, &batch.staged_path(name), manifest_ref, true);
+                    if result.is_ok() {
+                        this.update_progress(UpdateProgress::FinishFileDownload);
+                    }
+                    ((name, target), result)
+                })
+                .collect()
+        });
+
+        let mut errs = vec![];
+        let mut staged: Vec<(&TargetName, &Target)> = vec![];
+        for ((name, target), result) in results {
+            match result {
+                Ok(()) => staged.push((name, target)),
+                Err(err) => errs.push(err),
+            }
+        }
+
+        // As in the atomic path, a partial batch is never committed.
+        if !errs.is_empty() {
+            self.update_progress(UpdateProgress::FinishUpdate);
+            return (0, 0, errs);
+        }
+
+        for (name, _) in &staged {
+            if self.safe_delete_exe_target == name.resolved() {
+                self_replace::self_delete().expect("Self delete");
+                println!("Safe delete: {}", name.resolved());
             }
         }
-        let updated_files = targets.targets.len() - errs.len();
+
+        let staged_names: Vec<TargetName> = staged.iter().map(|(name, _)| (*name).clone()).collect();
+        if let Err(err) = batch.commit(&self.dist_dir, &staged_names) {
+            errs.push(err.context("failed to commit staged update"));
+            self.update_progress(UpdateProgress::FinishUpdate);
+            return (0, 0, errs);
+        }
+
+        for (name, target) in &staged {
+            manifest.set_target(name, target.length, &target.hashes.sha256);
+        }
+
+        let updated_files = staged.len();
+        let bytes = staged.iter().map(|(_, target)| target.length).sum();
         self.update_progress(UpdateProgress::FinishUpdate);
 
-        (updated_files, errs)
+        (updated_files, bytes, errs)
     }
 
     fn delete_target(&self, name: &TargetName) -> anyhow::Result<()> {
@@ -180,7 +702,7 @@ impl Updater {
             return Ok(UpdateResult::AlreadyUpdated);
         }
 
-        let (updated_files, update_errs) = self.update_all_targets(&mut manifest);
+        let (updated_files, bytes_transferred, update_errs) = self.update_all_targets(&mut manifest);
         let (deleted_files, deleted_errs) = self.delete_removed_targets(&mut manifest);
 
         let mut errs: Vec<anyhow::Error> = update_errs;
@@ -195,6 +717,7 @@ impl Updater {
             updated_files,
             deleted_files,
             update_time: start.elapsed(),
+            bytes_transferred,
         };
 
         Ok(if errs.is_empty() {
@@ -203,4 +726,98 @@ impl Updater {
             UpdateResult::IncompleteUpdate { errs, report }
         })
     }
+
+    /// Walks every entry recorded in the `Manifest`, recomputes its on-disk SHA256, and reports
+    /// mismatches, missing files, and extra files in `dist_dir` the manifest doesn't know about.
+    /// Unlike `update()`, this doesn't require the snapshot version to have changed, so it can
+    /// catch disk corruption or tampering between regular updates.
+    pub fn verify(&self) -> anyhow::Result<VerifyReport> {
+        let manifest = Manifest::load(&self.manifest_file)?;
+        Ok(self.verify_against(&manifest).0)
+    }
+
+    /// Runs the same reconciliation as `verify()`, then re-fetches (via the usual
+    /// streaming/verification path) every target whose on-disk hash or length diverged from the
+    /// manifest. Files the manifest doesn't know about are reported but left untouched.
+    pub fn repair(&self) -> anyhow::Result<VerifyReport> {
+        let manifest = Manifest::load(&self.manifest_file)?;
+        let (mut report, divergent) = self.verify_against(&manifest);
+
+        let targets = &self.repo.targets().signed;
+        for name in &divergent {
+            let Some(target) = targets.targets.get(name).map(|t| &t.signed) else {
+                continue;
+            };
+            let dest_path = self.dist_dir.join(name.resolved());
+            if self.download_target(name, target, &dest_path, &manifest, false).is_ok() {
+                report.repaired += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn verify_against(&self, manifest: &Manifest) -> (VerifyReport, Vec<TargetName>) {
+        let mut report = VerifyReport::default();
+        let mut divergent = vec![];
+
+        for (name, entry) in manifest.files() {
+            let path = self.dist_dir.join(name.resolved());
+            match Self::hash_file(&path) {
+                Ok(Some((len, hash))) if len == entry.length && hash == *entry.hash => {
+                    report.verified += 1;
+                }
+                Ok(Some(_)) => {
+                    report.mismatched += 1;
+                    divergent.push(name.clone());
+                }
+                Ok(None) => {
+                    report.missing += 1;
+                    divergent.push(name.clone());
+                }
+                Err(_) => {
+                    report.missing += 1;
+                    divergent.push(name.clone());
+                }
+            }
+        }
+
+        let known: HashSet<String> = manifest
+            .files()
+            .keys()
+            .map(|name| name.resolved().to_string())
+            .collect();
+        if let Ok(entries) = std::fs::read_dir(&self.dist_dir) {
+            report.extra = entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_name() != ".staging")
+                .filter(|entry| !known.contains(&entry.file_name().to_string_lossy().to_string()))
+                .count();
+        }
+
+        (report, divergent)
+    }
+
+    /// Computes the length and SHA256 digest of the file at `path`, or `None` if it's missing.
+    fn hash_file(path: &Path) -> std::io::Result<Option<(u64, Vec<u8>)>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut file = File::open(path)?;
+        let mut ctx = digest::Context::new(&digest::SHA256);
+        let mut buf = [0u8; DOWNLOAD_CHUNK_SIZE];
+        let mut len = 0u64;
+
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            ctx.update(&buf[..n]);
+            len += n as u64;
+        }
+
+        Ok(Some((len, ctx.finish().as_ref().to_vec())))
+    }
 }